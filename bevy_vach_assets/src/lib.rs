@@ -22,9 +22,17 @@ pub use vach;
 pub const ASSETS_DIR: &str = "assets";
 
 pub const ASSETS_ARCHIVE: &str = "assets.bva";
+// note: sidecar written next to the archive recording each path's leaf id, content hash,
+//       size and mtime; `bva archive` only uses it to report how many files are unchanged
+//       since the last build, every file is still re-compressed/re-encrypted
+pub const ASSETS_ARCHIVE_CATALOG: &str = "assets.bva.catalog";
 pub const ARCHIVE_DIR: &str = ".";
 pub const ARCHIVE_MAGIC: &[u8; vach::MAGIC_LENGTH] = b"BVA42"; // BVA = Bevy Vach Archive
 
+// note: optional; read by `bva archive` for include/exclude globs and per-glob
+//       compression/encryption overrides, see bva_cli::archive::BvaConfig
+pub const BVA_CONFIG: &str = "bva.toml";
+
 pub const SECRETS_DIR: &str = "secrets";
 pub const SECRETS_PUBLIC_KEY: &str = "key.pub";
 pub const SECRETS_PRIVATE_KEY: &str = "key.sec";
@@ -32,12 +40,20 @@ pub const SECRETS_KEY_PAIR: &str = "key.pair";
 
 pub const ASSET_FILE_INDEX: &str = "📇";
 pub const ASSET_FILE_INDEX_SEP: &str = "|BVA|";
+// note: maps a path to the leaf id holding its bytes; several paths may point at the same
+//       id when `bva archive` deduplicated byte-identical files
+pub const ASSET_FILE_INDEX_KV_SEP: &str = "|ID|";
 
 #[derive(Default, Debug, Clone)]
 pub struct BevyVachAssetsPlugin {
     // note: add properties if/when needed
     pub public_key_bytes: Option<&'static [u8; PUBLIC_KEY_LENGTH]>,
     pub static_archive: Option<&'static [u8]>,
+    // note: wasm32-only; when set (and no static_archive is embedded), the archive is fetched
+    //       over HTTP instead of being embedded: a Range request for its first 256 KiB, then
+    //       the whole file once a leaf outside that window is needed; leaves are not fetched
+    //       individually, and after widening the whole archive is held in memory
+    pub remote_url: Option<&'static str>,
 }
 
 impl Plugin for BevyVachAssetsPlugin {
@@ -49,9 +65,14 @@ impl Plugin for BevyVachAssetsPlugin {
         // needed to move the values into the closure
         let public_key_bytes = self.public_key_bytes;
         let static_archive = self.static_archive;
+        let remote_url = self.remote_url;
 
         let source = AssetSource::build().with_reader(move || {
-            Box::new(BevyVachAssetReader::new(public_key_bytes, static_archive))
+            Box::new(BevyVachAssetReader::new(
+                public_key_bytes,
+                static_archive,
+                remote_url,
+            ))
         });
         app.register_asset_source(AssetSourceId::Default, source);
     }
@@ -68,8 +89,14 @@ impl ReadExt for StdCursor<&'static [u8]> {}
 
 type Readable = Box<dyn ReadExt>;
 
+enum Backend {
+    Embedded(Archive<Readable>),
+    #[cfg(target_arch = "wasm32")]
+    Remote(wasm_remote::RemoteArchive),
+}
+
 struct BevyVachAssetReader {
-    archive: Archive<Readable>,
+    backend: Backend,
     lookup: HashMap<PathBuf, String>,
     fallback: Option<Box<dyn AssetReader>>,
 }
@@ -86,6 +113,7 @@ impl BevyVachAssetReader {
     pub fn new(
         public_key_bytes: Option<&'static [u8; PUBLIC_KEY_LENGTH]>,
         static_archive: Option<&'static [u8]>,
+        remote_url: Option<&'static str>,
     ) -> Self {
         // TODO: needs better setup handling! see pieces below
 
@@ -95,22 +123,33 @@ impl BevyVachAssetReader {
             .and_then(|b| VerifyingKey::from_bytes(b).ok())
             .map_or(config, |k| config.key(k));
 
-        // todo: find a reliable way to use fetch API instead of embedding the archive
-        // note: tried to use web-sys and wrapping in a TaskPool, but always panicked on
-        //       an option unwrap for results when awaiting the fetch; no idea what's up
-        let target = if let Some(archive) = static_archive {
-            let cursor = StdCursor::new(archive);
-            let boxed: Readable = Box::new(cursor);
-            boxed
-        } else if cfg!(target_arch = "wasm32") {
-            bevy::log::error!("no static/embedded archive found, but required for wasm target");
-            panic!("no static/embedded archive found, but required for wasm target")
+        if static_archive.is_none() && cfg!(target_arch = "wasm32") {
+            #[cfg(target_arch = "wasm32")]
+            if let Some(remote_url) = remote_url {
+                return Self {
+                    backend: Backend::Remote(wasm_remote::RemoteArchive::new(
+                        remote_url,
+                        public_key_bytes,
+                    )),
+                    lookup: HashMap::new(),
+                    fallback: None,
+                };
+            }
+
+            bevy::log::error!(
+                "no static/embedded archive or remote_url found, but one is required for wasm target"
+            );
+            panic!(
+                "no static/embedded archive or remote_url found, but one is required for wasm target"
+            )
+        }
+
+        let target: Readable = if let Some(archive) = static_archive {
+            Box::new(StdCursor::new(archive))
         } else {
             let dir = std::env::current_dir().expect("could not get current directory");
             let archive_path = dir.join(ARCHIVE_DIR).join(ASSETS_ARCHIVE);
-            let f = File::open(archive_path).expect("could not open the asset archive file");
-            let boxed: Readable = Box::new(f);
-            boxed
+            Box::new(File::open(archive_path).expect("could not open the asset archive file"))
         };
 
         let mut archive = Archive::with_config(target, &config).expect("oops");
@@ -122,12 +161,14 @@ impl BevyVachAssetReader {
         let files = files.split(ASSET_FILE_INDEX_SEP).collect::<Vec<_>>();
 
         let mut lookup = HashMap::new();
-        for (id, path) in files.iter().enumerate() {
-            lookup.insert(PathBuf::from(path), id.to_string());
+        for entry in files {
+            if let Some((path, id)) = entry.split_once(ASSET_FILE_INDEX_KV_SEP) {
+                lookup.insert(PathBuf::from(path), id.to_string());
+            }
         }
 
         Self {
-            archive,
+            backend: Backend::Embedded(archive),
             lookup,
             fallback: None,
         }
@@ -138,9 +179,10 @@ impl BevyVachAssetReader {
     pub fn new_with_fallback(
         public_key_bytes: Option<&'static [u8; vach::PUBLIC_KEY_LENGTH]>,
         static_archive: Option<&'static [u8]>,
+        remote_url: Option<&'static str>,
         mut fallback: impl FnMut() -> Box<dyn AssetReader> + Send + Sync + 'static,
     ) -> Self {
-        let mut reader = Self::new(public_key_bytes, static_archive);
+        let mut reader = Self::new(public_key_bytes, static_archive, remote_url);
         reader.fallback = Some(fallback());
         reader
     }
@@ -151,9 +193,12 @@ impl BevyVachAssetReader {
     ///
     /// This will returns an error if the path is not known.
     fn load_path_sync(&self, path: &Path) -> Result<DataReader, AssetReaderError> {
+        let Backend::Embedded(archive) = &self.backend else {
+            return Err(AssetReaderError::NotFound(path.to_path_buf()));
+        };
         self.lookup
             .get(path)
-            .and_then(|id| self.archive.fetch(id).ok())
+            .and_then(|id| archive.fetch(id).ok())
             .map(|r| DataReader::new(r.data))
             .ok_or_else(|| AssetReaderError::NotFound(path.to_path_buf()))
     }
@@ -163,39 +208,72 @@ impl BevyVachAssetReader {
     }
 
     fn is_directory_sync(&self, path: &Path) -> bool {
-        let as_folder = path.join("");
-        self.lookup
-            .keys()
-            .any(|loaded_path| loaded_path.starts_with(&as_folder) && loaded_path != &path)
+        is_directory_in(&self.lookup, path)
     }
 
     fn read_directory_sync(&self, path: &Path) -> Result<DirReader, AssetReaderError> {
         if self.is_directory_sync(path) {
-            let paths: Vec<_> = self
-                .lookup
-                .keys()
-                .filter(|loaded_path| loaded_path.starts_with(path))
-                .cloned()
-                .collect();
-            Ok(DirReader(paths))
+            Ok(DirReader(read_directory_in(&self.lookup, path)))
         } else {
             Err(AssetReaderError::NotFound(path.to_path_buf()))
         }
     }
 }
 
+/// Whether `path` is a folder in the virtual asset tree, i.e. some loaded path is nested
+/// under it. Shared with tooling (e.g. `bva shell`) that wants the same semantics Bevy
+/// sees at runtime.
+pub fn is_directory_in<M>(lookup: &M, path: &Path) -> bool
+where
+    for<'a> &'a M: IntoIterator<Item = (&'a PathBuf, &'a String)>,
+{
+    let as_folder = path.join("");
+    lookup
+        .into_iter()
+        .any(|(loaded_path, _)| loaded_path.starts_with(&as_folder) && loaded_path != path)
+}
+
+/// List every loaded path nested under `path`.
+pub fn read_directory_in<M>(lookup: &M, path: &Path) -> Vec<PathBuf>
+where
+    for<'a> &'a M: IntoIterator<Item = (&'a PathBuf, &'a String)>,
+{
+    lookup
+        .into_iter()
+        .filter(|(loaded_path, _)| loaded_path.starts_with(path))
+        .map(|(loaded_path, _)| loaded_path.clone())
+        .collect()
+}
+
 impl AssetReader for BevyVachAssetReader {
     fn read<'a>(
         &'a self,
         path: &'a Path,
     ) -> bevy::utils::BoxedFuture<'a, Result<Box<Reader<'a>>, AssetReaderError>> {
-        if self.has_file_sync(path) {
-            Box::pin(async move { self.load_path_sync(path).map(|reader| reader.boxed()) })
-        } else if let Some(fallback) = self.fallback.as_ref() {
-            fallback.read(path)
-        } else {
-            Box::pin(async move { Err(AssetReaderError::NotFound(path.to_path_buf())) })
-        }
+        Box::pin(async move {
+            match &self.backend {
+                Backend::Embedded(_) => {
+                    if self.has_file_sync(path) {
+                        self.load_path_sync(path).map(|reader| reader.boxed())
+                    } else if let Some(fallback) = self.fallback.as_ref() {
+                        fallback.read(path).await
+                    } else {
+                        Err(AssetReaderError::NotFound(path.to_path_buf()))
+                    }
+                }
+                #[cfg(target_arch = "wasm32")]
+                Backend::Remote(remote) => match remote.fetch(path).await {
+                    Ok(data) => Ok(DataReader::new(data).boxed()),
+                    Err(err) => {
+                        if let Some(fallback) = self.fallback.as_ref() {
+                            fallback.read(path).await
+                        } else {
+                            Err(err)
+                        }
+                    }
+                },
+            }
+        })
     }
 
     fn read_meta<'a>(
@@ -204,13 +282,30 @@ impl AssetReader for BevyVachAssetReader {
     ) -> bevy::utils::BoxedFuture<'a, Result<Box<Reader<'a>>, AssetReaderError>> {
         let meta_path = get_meta_path(path);
 
-        if self.has_file_sync(&meta_path) {
-            Box::pin(async move { self.load_path_sync(&meta_path).map(|reader| reader.boxed()) })
-        } else if let Some(fallback) = self.fallback.as_ref() {
-            fallback.read_meta(path)
-        } else {
-            Box::pin(async move { Err(AssetReaderError::NotFound(meta_path)) })
-        }
+        Box::pin(async move {
+            match &self.backend {
+                Backend::Embedded(_) => {
+                    if self.has_file_sync(&meta_path) {
+                        self.load_path_sync(&meta_path).map(|reader| reader.boxed())
+                    } else if let Some(fallback) = self.fallback.as_ref() {
+                        fallback.read_meta(path).await
+                    } else {
+                        Err(AssetReaderError::NotFound(meta_path))
+                    }
+                }
+                #[cfg(target_arch = "wasm32")]
+                Backend::Remote(remote) => match remote.fetch(&meta_path).await {
+                    Ok(data) => Ok(DataReader::new(data).boxed()),
+                    Err(err) => {
+                        if let Some(fallback) = self.fallback.as_ref() {
+                            fallback.read_meta(path).await
+                        } else {
+                            Err(err)
+                        }
+                    }
+                },
+            }
+        })
     }
 
     fn read_directory<'a>(
@@ -218,10 +313,23 @@ impl AssetReader for BevyVachAssetReader {
         path: &'a Path,
     ) -> bevy::utils::BoxedFuture<'a, Result<Box<PathStream>, AssetReaderError>> {
         Box::pin(async move {
-            self.read_directory_sync(path).map(|read_dir| {
-                let boxed: Box<PathStream> = Box::new(read_dir);
-                boxed
-            })
+            match &self.backend {
+                Backend::Embedded(_) => self.read_directory_sync(path).map(|read_dir| {
+                    let boxed: Box<PathStream> = Box::new(read_dir);
+                    boxed
+                }),
+                #[cfg(target_arch = "wasm32")]
+                Backend::Remote(remote) => {
+                    let lookup = remote.lookup().await?;
+                    if is_directory_in(&lookup, path) {
+                        let boxed: Box<PathStream> =
+                            Box::new(DirReader(read_directory_in(&lookup, path)));
+                        Ok(boxed)
+                    } else {
+                        Err(AssetReaderError::NotFound(path.to_path_buf()))
+                    }
+                }
+            }
         })
     }
 
@@ -229,7 +337,16 @@ impl AssetReader for BevyVachAssetReader {
         &'a self,
         path: &'a Path,
     ) -> bevy::utils::BoxedFuture<'a, Result<bool, AssetReaderError>> {
-        Box::pin(async move { Ok(self.is_directory_sync(path)) })
+        Box::pin(async move {
+            match &self.backend {
+                Backend::Embedded(_) => Ok(self.is_directory_sync(path)),
+                #[cfg(target_arch = "wasm32")]
+                Backend::Remote(remote) => {
+                    let lookup = remote.lookup().await?;
+                    Ok(is_directory_in(&lookup, path))
+                }
+            }
+        })
     }
 }
 
@@ -277,3 +394,196 @@ fn get_meta_path(path: &Path) -> PathBuf {
     meta_path.set_extension(extension);
     meta_path
 }
+
+#[cfg(target_arch = "wasm32")]
+mod wasm_remote {
+    //! Loads a remote `.bva` over HTTP instead of embedding it in the wasm build: one Range
+    //! request for the first `INITIAL_FETCH_LEN` bytes, widened to a fetch of the whole file
+    //! when a requested leaf (or the file index) lies beyond that window. Leaves are not
+    //! fetched individually; once widened, the whole archive is held in memory.
+    //!
+    //! note: an earlier version of this module hand-rolled vach's on-disk registry format
+    //!       to slice out exact per-leaf byte ranges, but vach's public API doesn't expose
+    //!       raw leaf offsets, and duplicating its decrypt/decompress ordering from outside
+    //!       the crate got that ordering wrong. Instead we fetch a prefix of the remote
+    //!       file (or the whole file) and hand it to a real `vach::archive::Archive`, so decryption,
+    //!       decompression and signature verification all go through the same code path
+    //!       the embedded/local backend uses. That means the first load of any leaf may
+    //!       pull in more bytes than strictly necessary, but it actually decodes correctly.
+
+    use super::{ARCHIVE_MAGIC, ASSET_FILE_INDEX, ASSET_FILE_INDEX_KV_SEP, ASSET_FILE_INDEX_SEP};
+    use async_lock::Mutex;
+    use bevy::asset::io::AssetReaderError;
+    use js_sys::Uint8Array;
+    use std::{
+        collections::HashMap,
+        io::Cursor,
+        path::{Path, PathBuf},
+        sync::Arc,
+    };
+    use vach::{
+        archive::{Archive, ArchiveConfig},
+        prelude::VerifyingKey,
+        PUBLIC_KEY_LENGTH,
+    };
+    use wasm_bindgen::{JsCast, JsValue};
+    use wasm_bindgen_futures::JsFuture;
+    use web_sys::{Request, RequestInit, RequestMode, Response};
+
+    // note: most archives built for this crate's examples are a few hundred KB; start with
+    //       a window that comfortably covers header + registry + a handful of small leaves
+    //       before falling back to fetching the whole file.
+    const INITIAL_FETCH_LEN: u64 = 256 * 1024;
+
+    struct CachedArchive {
+        full: bool,
+        archive: Archive<Cursor<Vec<u8>>>,
+        lookup: HashMap<PathBuf, String>,
+    }
+
+    /// Lazily fetched mirror of a remote `.bva`, grown on demand as leaves are requested.
+    pub(crate) struct RemoteArchive {
+        base_url: &'static str,
+        public_key_bytes: Option<&'static [u8; PUBLIC_KEY_LENGTH]>,
+        state: Mutex<Option<CachedArchive>>,
+    }
+
+    impl RemoteArchive {
+        pub(crate) fn new(
+            base_url: &'static str,
+            public_key_bytes: Option<&'static [u8; PUBLIC_KEY_LENGTH]>,
+        ) -> Self {
+            Self {
+                base_url,
+                public_key_bytes,
+                state: Mutex::new(None),
+            }
+        }
+
+        fn config(&self) -> ArchiveConfig {
+            let config = ArchiveConfig::default().magic(*ARCHIVE_MAGIC);
+            // todo: currently it silently fails if the key is not valid, same as the
+            //       embedded backend in `BevyVachAssetReader::new`
+            self.public_key_bytes
+                .and_then(|b| VerifyingKey::from_bytes(b).ok())
+                .map_or(config, |k| config.key(k))
+        }
+
+        /// Fetches (or re-fetches, widening to the whole file) the archive bytes and
+        /// rebuilds the path -> leaf id lookup from the `ASSET_FILE_INDEX` leaf.
+        async fn load(&self, full: bool) -> Result<(), AssetReaderError> {
+            let bytes = if full {
+                fetch_all(self.base_url)
+                    .await
+                    .map_err(|_| io_err("failed to fetch the full archive over HTTP"))?
+            } else {
+                fetch_range(self.base_url, 0, INITIAL_FETCH_LEN - 1)
+                    .await
+                    .map_err(|_| io_err("failed to fetch archive bytes over HTTP"))?
+            };
+
+            let mut archive = Archive::with_config(Cursor::new(bytes), &self.config())
+                .map_err(|_| io_err("failed to parse archive header/registry from the fetched bytes"))?;
+
+            let mut lookup = HashMap::new();
+            if let Ok(file_index) = archive.fetch_mut(ASSET_FILE_INDEX) {
+                let files = String::from_utf8_lossy(&file_index.data).into_owned();
+                for entry in files.split(ASSET_FILE_INDEX_SEP) {
+                    if let Some((path, id)) = entry.split_once(ASSET_FILE_INDEX_KV_SEP) {
+                        lookup.insert(PathBuf::from(path), id.to_string());
+                    }
+                }
+            }
+
+            *self.state.lock().await = Some(CachedArchive {
+                full,
+                archive,
+                lookup,
+            });
+            Ok(())
+        }
+
+        /// Snapshot of the path -> leaf id map, loading the (small) initial window first
+        /// if nothing has been fetched yet. Used to answer `is_directory`/`read_directory`
+        /// without needing a specific leaf's bytes.
+        pub(crate) async fn lookup(&self) -> Result<HashMap<PathBuf, String>, AssetReaderError> {
+            if self.state.lock().await.is_none() {
+                self.load(false).await?;
+            }
+            let state = self.state.lock().await;
+            Ok(state.as_ref().expect("just populated above").lookup.clone())
+        }
+
+        pub(crate) async fn fetch(&self, path: &Path) -> Result<Box<[u8]>, AssetReaderError> {
+            if self.state.lock().await.is_none() {
+                self.load(false).await?;
+            }
+
+            {
+                let mut state = self.state.lock().await;
+                let cached = state.as_mut().expect("just populated above");
+                if let Some(id) = cached.lookup.get(path).cloned() {
+                    if let Ok(resource) = cached.archive.fetch(&id) {
+                        return Ok(resource.data);
+                    }
+                }
+                if cached.full {
+                    return Err(AssetReaderError::NotFound(path.to_path_buf()));
+                }
+            }
+
+            // the initial window didn't cover this leaf's bytes (or the file index that
+            // maps the path to it); widen to the whole file and retry once.
+            self.load(true).await?;
+            let mut state = self.state.lock().await;
+            let cached = state.as_mut().expect("just populated above");
+            let id = cached
+                .lookup
+                .get(path)
+                .cloned()
+                .ok_or_else(|| AssetReaderError::NotFound(path.to_path_buf()))?;
+            cached
+                .archive
+                .fetch(&id)
+                .map(|resource| resource.data)
+                .map_err(|_| AssetReaderError::NotFound(path.to_path_buf()))
+        }
+    }
+
+    fn io_err(message: &str) -> AssetReaderError {
+        AssetReaderError::Io(Arc::new(std::io::Error::new(
+            std::io::ErrorKind::InvalidData,
+            message,
+        )))
+    }
+
+    async fn fetch_range(url: &str, start: u64, end: u64) -> Result<Vec<u8>, JsValue> {
+        let opts = RequestInit::new();
+        opts.set_method("GET");
+        opts.set_mode(RequestMode::Cors);
+
+        let request = Request::new_with_str_and_init(url, &opts)?;
+        request
+            .headers()
+            .set("Range", &format!("bytes={start}-{end}"))?;
+
+        fetch(&request).await
+    }
+
+    async fn fetch_all(url: &str) -> Result<Vec<u8>, JsValue> {
+        let opts = RequestInit::new();
+        opts.set_method("GET");
+        opts.set_mode(RequestMode::Cors);
+
+        let request = Request::new_with_str_and_init(url, &opts)?;
+        fetch(&request).await
+    }
+
+    async fn fetch(request: &Request) -> Result<Vec<u8>, JsValue> {
+        let window = web_sys::window().expect("wasm32 target always has a window");
+        let response_value = JsFuture::from(window.fetch_with_request(request)).await?;
+        let response: Response = response_value.dyn_into()?;
+        let buffer = JsFuture::from(response.array_buffer()?).await?;
+        Ok(Uint8Array::new(&buffer).to_vec())
+    }
+}
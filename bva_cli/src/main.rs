@@ -32,15 +32,41 @@ enum Commands {
         /// Check archive file list after creation
         #[arg(short, long)]
         check: bool,
+
+        /// Skip comparing against the catalog sidecar from the last run
+        ///
+        /// note: every file is re-compressed/re-encrypted on every run regardless of
+        /// this flag; the catalog only changes what gets reported as "unchanged", not
+        /// what gets done, since vach doesn't expose a way to copy an already-encoded
+        /// leaf into a new archive verbatim
+        #[arg(long)]
+        full: bool,
     },
 
     /// Check archive file list
     #[command(name = "check")]
     CheckFiles {},
 
+    /// Unpack an archive back to a directory
+    Extract {
+        /// Directory to extract assets into
+        out_dir: PathBuf,
+
+        /// Only extract paths matching this glob pattern
+        #[arg(short, long)]
+        filter: Option<String>,
+
+        /// Overwrite existing files in the output directory
+        #[arg(long)]
+        force: bool,
+    },
+
     /// Generate keys for encryption and signing
     #[command(name = "generate")]
     GenerateKeys {},
+
+    /// Open an interactive shell for browsing an archive without extracting it
+    Shell {},
 }
 
 #[derive(Args, Clone, Debug)]
@@ -60,8 +86,8 @@ fn main() -> anyhow::Result<()> {
     let globals = &args.global;
 
     match args.command {
-        Commands::Archive { check, .. } => {
-            archive::run(globals)?;
+        Commands::Archive { check, full } => {
+            archive::run(globals, full)?;
             if check {
                 check_files::run(globals)?;
             }
@@ -71,9 +97,21 @@ fn main() -> anyhow::Result<()> {
             check_files::run(globals)?;
         }
 
+        Commands::Extract {
+            out_dir,
+            filter,
+            force,
+        } => {
+            extract::run(globals, &out_dir, filter.as_deref(), force)?;
+        }
+
         Commands::GenerateKeys { .. } => {
             generate::run(globals)?;
         }
+
+        Commands::Shell { .. } => {
+            shell::run(globals)?;
+        }
     }
 
     Ok(())
@@ -87,19 +125,169 @@ mod archive {
             prelude::{Builder, BuilderConfig, SigningKey},
             SIGNATURE_LENGTH,
         },
-        ARCHIVE_MAGIC, ASSETS_ARCHIVE, ASSETS_DIR, ASSET_FILE_INDEX, ASSET_FILE_INDEX_SEP,
-        SECRETS_KEY_PAIR,
+        ARCHIVE_MAGIC, ASSETS_ARCHIVE, ASSETS_ARCHIVE_CATALOG, ASSETS_DIR, ASSET_FILE_INDEX,
+        ASSET_FILE_INDEX_KV_SEP, ASSET_FILE_INDEX_SEP, BVA_CONFIG, SECRETS_KEY_PAIR,
     };
+    use glob::Pattern;
     use normpath::PathExt;
     use path_slash::PathExt as _;
+    use serde::Deserialize;
     use std::{
+        collections::HashMap,
         env::current_dir,
         fs::File,
         io::{Cursor, Read},
+        time::UNIX_EPOCH,
     };
     use walkdir::{DirEntry, WalkDir};
 
-    pub(crate) fn run(globals: &GlobalArgs) -> anyhow::Result<()> {
+    /// One row of the `assets.bva.catalog` sidecar: what a path looked like (and which
+    /// leaf id it was stored under) the last time `bva archive` ran.
+    #[derive(Clone, Debug)]
+    struct CatalogEntry {
+        id: String,
+        hash: String,
+        size: u64,
+        mtime: u64,
+    }
+
+    /// `bva.toml`: optional include/exclude globs plus per-glob compression/encryption
+    /// overrides, read from the project root.
+    #[derive(Debug, Deserialize)]
+    #[serde(default)]
+    struct BvaConfig {
+        include: Vec<String>,
+        exclude: Vec<String>,
+        #[serde(rename = "rule")]
+        rules: Vec<CompressionRule>,
+    }
+
+    impl Default for BvaConfig {
+        fn default() -> Self {
+            // note: used when there is no bva.toml (or it sets no `exclude`): skips every
+            //       file under a node_modules/ or target/ folder and every .xcf file;
+            //       dotfiles and dot-folders are skipped separately by `is_hidden`
+            Self {
+                include: Vec::new(),
+                exclude: vec![
+                    "**/node_modules/**".into(),
+                    "**/target/**".into(),
+                    "**/*.xcf".into(),
+                ],
+                rules: Vec::new(),
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Deserialize)]
+    struct CompressionRule {
+        pattern: String,
+        compress: Option<CompressModeConfig>,
+        algorithm: Option<CompressionAlgorithmConfig>,
+        level: Option<u32>,
+        encrypt: Option<bool>,
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum CompressModeConfig {
+        Always,
+        Detect,
+        Never,
+    }
+
+    impl From<CompressModeConfig> for CompressMode {
+        fn from(value: CompressModeConfig) -> Self {
+            match value {
+                CompressModeConfig::Always => CompressMode::Always,
+                CompressModeConfig::Detect => CompressMode::Detect,
+                CompressModeConfig::Never => CompressMode::Never,
+            }
+        }
+    }
+
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Deserialize)]
+    #[serde(rename_all = "lowercase")]
+    enum CompressionAlgorithmConfig {
+        Brotli,
+        Lz4,
+        Snappy,
+    }
+
+    impl CompressionAlgorithmConfig {
+        fn into_algorithm(self, level: u32) -> CompressionAlgorithm {
+            match self {
+                Self::Brotli => CompressionAlgorithm::Brotli(level),
+                Self::Lz4 => CompressionAlgorithm::LZ4,
+                Self::Snappy => CompressionAlgorithm::Snappy,
+            }
+        }
+    }
+
+    impl BvaConfig {
+        fn load(path: &std::path::Path) -> anyhow::Result<Self> {
+            let Ok(raw) = std::fs::read_to_string(path) else {
+                return Ok(Self::default());
+            };
+            Ok(toml::from_str(&raw)?)
+        }
+
+        fn is_excluded(&self, path: &str) -> bool {
+            self.exclude.iter().any(|glob| glob_matches(glob, path))
+        }
+
+        fn is_included(&self, path: &str) -> bool {
+            self.include.is_empty() || self.include.iter().any(|glob| glob_matches(glob, path))
+        }
+
+        /// Apply the first-to-last matching rule's overrides on top of the base template,
+        /// also returning a [`LeafSignature`] describing what was applied, so callers can
+        /// tell whether two paths ended up with the same effective leaf config.
+        fn leaf_for(&self, path: &str, template: &Leaf) -> (Leaf, LeafSignature) {
+            let mut leaf = template.clone();
+            let mut signature = LeafSignature::default();
+            for rule in self.rules.iter().filter(|rule| glob_matches(&rule.pattern, path)) {
+                if let Some(mode) = rule.compress {
+                    leaf = leaf.compress(mode.into());
+                    signature.compress = Some(mode);
+                }
+                // a rule that only sets `level` still needs to pick an algorithm to apply
+                // it to; default to Brotli, the same algorithm the base template uses
+                let algorithm = rule
+                    .algorithm
+                    .or(rule.level.is_some().then_some(CompressionAlgorithmConfig::Brotli));
+                if let Some(algorithm) = algorithm {
+                    let level = rule.level.unwrap_or(9);
+                    leaf = leaf.compression_algo(algorithm.into_algorithm(level));
+                    signature.algorithm = Some(algorithm);
+                    signature.level = Some(level);
+                }
+                if let Some(encrypt) = rule.encrypt {
+                    leaf = leaf.encrypt(encrypt);
+                    signature.encrypt = Some(encrypt);
+                }
+            }
+            (leaf, signature)
+        }
+    }
+
+    /// The subset of a resolved [`Leaf`]'s config that affects its stored bytes, used to
+    /// tell whether two byte-identical files can safely share one leaf: `Leaf` itself
+    /// doesn't implement `Eq`/`Hash`, and two paths with the same content can still match
+    /// different `bva.toml` rules.
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Hash)]
+    struct LeafSignature {
+        compress: Option<CompressModeConfig>,
+        algorithm: Option<CompressionAlgorithmConfig>,
+        level: Option<u32>,
+        encrypt: Option<bool>,
+    }
+
+    fn glob_matches(pattern: &str, path: &str) -> bool {
+        Pattern::new(pattern).is_ok_and(|pattern| pattern.matches(path))
+    }
+
+    pub(crate) fn run(globals: &GlobalArgs, full: bool) -> anyhow::Result<()> {
         let dir = current_dir()?;
         let assets_path = dir.join(&globals.assets_dir);
         let archive_path = dir
@@ -107,6 +295,9 @@ mod archive {
             .join(ASSETS_ARCHIVE)
             .normalize_virtually()?
             .into_path_buf();
+        let catalog_path = dir
+            .join(&globals.assets_archive_dir)
+            .join(ASSETS_ARCHIVE_CATALOG);
         let key_pair_path = dir.join(&globals.secrets_dir).join(SECRETS_KEY_PAIR);
         let mut issues = Vec::new();
 
@@ -147,20 +338,79 @@ mod archive {
             .sign(true)
             .version(1);
 
-        let mut builder = Builder::new().template(template);
+        let bva_config = BvaConfig::load(&dir.join(BVA_CONFIG))?;
+
+        let previous_catalog = if full {
+            HashMap::new()
+        } else {
+            read_catalog(&catalog_path)
+        };
+
+        let mut builder = Builder::new().template(template.clone());
         let mut files = Vec::new();
+        let mut catalog = Vec::new();
+        // note: content-addressed by (blake3 digest, resolved leaf config) so byte-identical
+        //       assets (shared textures, duplicated meta files, ...) are only compressed/
+        //       encrypted once — but only when they also resolved to the same bva.toml
+        //       rules, since otherwise the second path would silently inherit the first's
+        //       compression/encryption settings instead of its own
+        let mut seen_hashes: HashMap<(blake3::Hash, LeafSignature), String> = HashMap::new();
+        let mut next_id = 0usize;
+        // note: this only counts files whose content matches the last run's catalog; it
+        //       doesn't skip re-compressing/re-encrypting them (vach doesn't expose a way
+        //       to copy an already-encoded leaf into a new archive verbatim), so it's
+        //       reported purely as information, not as work or time saved
+        let mut unchanged_count = 0usize;
 
         let walker = WalkDir::new(ASSETS_DIR).follow_links(true).into_iter();
         for entry in walker.filter_entry(|e| !is_hidden(e)) {
             let entry = entry?;
             let path = entry.path().strip_prefix(ASSETS_DIR)?.to_slash_lossy();
-            if should_skip(&entry) {
+            if should_skip(&entry, &bva_config, path.as_ref()) {
                 continue;
             };
-            // let id = unsafe { String::from_utf8_unchecked(smaz::compress(id.as_bytes())) };
-            let id = files.len().to_string();
-            builder.add(File::open(entry.path())?, id)?;
-            files.push(path.to_string());
+
+            let metadata = entry.metadata()?;
+            let size = metadata.len();
+            let mtime = metadata.modified()?.duration_since(UNIX_EPOCH)?.as_secs();
+
+            let mut bytes = Vec::new();
+            File::open(entry.path())?.read_to_end(&mut bytes)?;
+            let hash = blake3::hash(&bytes);
+            let hash_hex = hash.to_hex().to_string();
+            let (leaf, signature) = bva_config.leaf_for(path.as_ref(), &template);
+
+            if previous_catalog.get(path.as_ref()).is_some_and(|prev| {
+                prev.hash == hash_hex && prev.size == size && prev.mtime == mtime
+            }) {
+                unchanged_count += 1;
+            }
+
+            // note: vach doesn't expose a way to copy an already-compressed/encrypted leaf
+            //       into a new archive verbatim, so even an unchanged file still has to go
+            //       through the builder below; only byte-identical content resolved to the
+            //       same leaf config is actually skipped, via the dedup above
+            let dedup_key = (hash, signature);
+            let id = if let Some(existing_id) = seen_hashes.get(&dedup_key) {
+                existing_id.clone()
+            } else {
+                let id = next_id.to_string();
+                next_id += 1;
+                builder.add_with_leaf(Cursor::new(bytes), id.clone(), leaf)?;
+                seen_hashes.insert(dedup_key, id.clone());
+                id
+            };
+
+            files.push(format!("{path}{ASSET_FILE_INDEX_KV_SEP}{id}"));
+            catalog.push((
+                path.to_string(),
+                CatalogEntry {
+                    id,
+                    hash: hash_hex,
+                    size,
+                    mtime,
+                },
+            ));
         }
 
         let data = Cursor::new(files.join(ASSET_FILE_INDEX_SEP).into_bytes());
@@ -168,9 +418,52 @@ mod archive {
 
         let mut target = File::create(&archive_path)?;
         builder.dump(&mut target, &config)?;
+        write_catalog(&catalog_path, &catalog)?;
+
+        println!(
+            "Created archive in '{}' ({unchanged_count} file(s) unchanged since the last run)",
+            archive_path.to_string_lossy()
+        );
+
+        Ok(())
+    }
+
+    fn read_catalog(path: &std::path::Path) -> HashMap<String, CatalogEntry> {
+        let Ok(content) = std::fs::read_to_string(path) else {
+            return HashMap::new();
+        };
 
-        println!("Created archive in '{}'", archive_path.to_string_lossy());
+        let mut catalog = HashMap::new();
+        for line in content.lines() {
+            let fields: Vec<_> = line.split(ASSET_FILE_INDEX_SEP).collect();
+            let [path, id, hash, size, mtime] = fields[..] else {
+                continue;
+            };
+            let (Ok(size), Ok(mtime)) = (size.parse(), mtime.parse()) else {
+                continue;
+            };
+            catalog.insert(
+                path.to_string(),
+                CatalogEntry {
+                    id: id.to_string(),
+                    hash: hash.to_string(),
+                    size,
+                    mtime,
+                },
+            );
+        }
+        catalog
+    }
 
+    fn write_catalog(path: &std::path::Path, catalog: &[(String, CatalogEntry)]) -> anyhow::Result<()> {
+        let mut out = String::new();
+        for (file_path, entry) in catalog {
+            out.push_str(&format!(
+                "{file_path}{ASSET_FILE_INDEX_SEP}{}{ASSET_FILE_INDEX_SEP}{}{ASSET_FILE_INDEX_SEP}{}{ASSET_FILE_INDEX_SEP}{}\n",
+                entry.id, entry.hash, entry.size, entry.mtime
+            ));
+        }
+        std::fs::write(path, out)?;
         Ok(())
     }
 
@@ -182,15 +475,8 @@ mod archive {
             .is_some_and(|s| s.starts_with('.'))
     }
 
-    // todo: make this configurable
-    fn should_skip(entry: &DirEntry) -> bool {
-        const SKIP_EXACT: &[&str] = &[".git", "node_modules", "target"];
-        const SKIP_EXTENSIONS: &[&str] = &[".xcf"];
-
-        entry.file_type().is_dir()
-            || entry.file_name().to_str().is_some_and(|s| {
-                SKIP_EXACT.contains(&s) || SKIP_EXTENSIONS.iter().any(|&skip| s.ends_with(skip))
-            })
+    fn should_skip(entry: &DirEntry, config: &BvaConfig, path: &str) -> bool {
+        entry.file_type().is_dir() || config.is_excluded(path) || !config.is_included(path)
     }
 }
 
@@ -202,7 +488,8 @@ mod check_files {
             archive::{Archive, ArchiveConfig},
             crypto::VerifyingKey,
         },
-        ARCHIVE_MAGIC, ASSETS_ARCHIVE, ASSET_FILE_INDEX, ASSET_FILE_INDEX_SEP, SECRETS_PUBLIC_KEY,
+        ARCHIVE_MAGIC, ASSETS_ARCHIVE, ASSET_FILE_INDEX, ASSET_FILE_INDEX_KV_SEP,
+        ASSET_FILE_INDEX_SEP, SECRETS_PUBLIC_KEY,
     };
     use std::{env::current_dir, fs::File, io::Read};
 
@@ -247,15 +534,134 @@ mod check_files {
         let files = files.split(ASSET_FILE_INDEX_SEP).collect::<Vec<_>>();
 
         println!("Files in archive:");
-        for (i, file) in files.iter().enumerate() {
-            let f = archive.fetch_mut(&i.to_string())?;
-            println!("-> {} [{}]", file, f.data.len());
+        for entry in files.iter() {
+            let Some((path, id)) = entry.split_once(ASSET_FILE_INDEX_KV_SEP) else {
+                continue;
+            };
+            let f = archive.fetch_mut(id)?;
+            println!("-> {} [{}] (id {})", path, f.data.len(), id);
         }
 
         Ok(())
     }
 }
 
+mod extract {
+    use crate::GlobalArgs;
+    use bevy_vach_assets::{
+        vach::{
+            self,
+            archive::{Archive, ArchiveConfig},
+            crypto::VerifyingKey,
+        },
+        ARCHIVE_MAGIC, ASSETS_ARCHIVE, ASSET_FILE_INDEX, ASSET_FILE_INDEX_KV_SEP,
+        ASSET_FILE_INDEX_SEP, SECRETS_PUBLIC_KEY,
+    };
+    use glob::Pattern;
+    use std::{
+        env::current_dir,
+        fs::{self, File},
+        io::{Read, Write},
+        path::{Component, Path},
+    };
+
+    pub(crate) fn run(
+        globals: &GlobalArgs,
+        out_dir: &Path,
+        filter: Option<&str>,
+        force: bool,
+    ) -> anyhow::Result<()> {
+        let dir = current_dir()?;
+        let archive_path = dir.join(&globals.assets_archive_dir).join(ASSETS_ARCHIVE);
+        let public_key_path = dir.join(&globals.secrets_dir).join(SECRETS_PUBLIC_KEY);
+        let mut issues = Vec::new();
+
+        if !archive_path.exists() {
+            issues.push(format!(
+                "Archive file '{}' not found",
+                archive_path.to_string_lossy()
+            ));
+        }
+        if !public_key_path.exists() {
+            issues.push(format!(
+                "Public key file '{}' not found",
+                public_key_path.to_string_lossy()
+            ));
+        }
+        if !issues.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Cannot extract archive due to the following issues:\n{}",
+                issues.join("\n")
+            ));
+        }
+
+        let pattern = filter.map(Pattern::new).transpose()?;
+        let archive_label = archive_path.to_string_lossy().into_owned();
+
+        let mut public_key_file = File::open(public_key_path)?;
+        let mut public_key_bytes = [0u8; vach::PUBLIC_KEY_LENGTH];
+        public_key_file.read_exact(&mut public_key_bytes)?;
+        let public_key = VerifyingKey::from_bytes(&public_key_bytes)?;
+
+        let config = ArchiveConfig::default()
+            .magic(*ARCHIVE_MAGIC)
+            .key(public_key);
+        let target = File::open(archive_path)?;
+        let mut archive = Archive::with_config(target, &config)?;
+
+        let file_index = archive.fetch_mut(ASSET_FILE_INDEX)?;
+        let files = String::from_utf8_lossy(&file_index.data).into_owned();
+        let files = files.split(ASSET_FILE_INDEX_SEP).collect::<Vec<_>>();
+
+        let mut extracted = 0usize;
+        for entry in files {
+            let Some((path, id)) = entry.split_once(ASSET_FILE_INDEX_KV_SEP) else {
+                continue;
+            };
+            if pattern.as_ref().is_some_and(|p| !p.matches(path)) {
+                continue;
+            }
+
+            // the index is plain data read off disk, so treat entries as untrusted:
+            // reject anything that could escape out_dir (`..`, an absolute path, ...)
+            // before it's ever joined onto a filesystem path
+            if !is_safe_relative_path(Path::new(path)) {
+                println!("Skipping '{path}': not a safe relative path");
+                continue;
+            }
+
+            let dest = out_dir.join(path);
+            if dest.exists() && !force {
+                println!(
+                    "Skipping existing file '{}' (use --force to overwrite)",
+                    dest.to_string_lossy()
+                );
+                continue;
+            }
+
+            if let Some(parent) = dest.parent() {
+                fs::create_dir_all(parent)?;
+            }
+
+            // note: vach only hands back the fully decoded leaf, so there's no chunked
+            //       read to stream here; this still avoids an extra buffer copy by writing
+            //       straight into the destination file
+            let resource = archive.fetch_mut(id)?;
+            let mut out = File::create(&dest)?;
+            out.write_all(&resource.data)?;
+            extracted += 1;
+        }
+
+        println!("Extracted {extracted} file(s) from '{archive_label}' into '{}'", out_dir.to_string_lossy());
+
+        Ok(())
+    }
+
+    fn is_safe_relative_path(path: &Path) -> bool {
+        path.components().all(|c| matches!(c, Component::Normal(_)))
+    }
+}
+
 mod generate {
     use crate::GlobalArgs;
     use bevy_vach_assets::{vach, SECRETS_KEY_PAIR, SECRETS_PRIVATE_KEY, SECRETS_PUBLIC_KEY};
@@ -296,3 +702,263 @@ mod generate {
         Ok(())
     }
 }
+
+mod shell {
+    use crate::GlobalArgs;
+    use bevy_vach_assets::{
+        is_directory_in,
+        vach::{
+            self,
+            archive::{Archive, ArchiveConfig},
+            crypto::VerifyingKey,
+        },
+        read_directory_in, ARCHIVE_MAGIC, ASSETS_ARCHIVE, ASSET_FILE_INDEX,
+        ASSET_FILE_INDEX_KV_SEP, ASSET_FILE_INDEX_SEP, SECRETS_PUBLIC_KEY,
+    };
+    use glob::Pattern;
+    use std::{
+        collections::HashMap,
+        env::current_dir,
+        fs::File,
+        io::{self, Read, Write},
+        path::{Path, PathBuf},
+    };
+
+    pub(crate) fn run(globals: &GlobalArgs) -> anyhow::Result<()> {
+        let dir = current_dir()?;
+        let archive_path = dir.join(&globals.assets_archive_dir).join(ASSETS_ARCHIVE);
+        let public_key_path = dir.join(&globals.secrets_dir).join(SECRETS_PUBLIC_KEY);
+        let mut issues = Vec::new();
+
+        if !archive_path.exists() {
+            issues.push(format!(
+                "Archive file '{}' not found",
+                archive_path.to_string_lossy()
+            ));
+        }
+        if !public_key_path.exists() {
+            issues.push(format!(
+                "Public key file '{}' not found",
+                public_key_path.to_string_lossy()
+            ));
+        }
+        if !issues.is_empty() {
+            return Err(anyhow::anyhow!(
+                "Cannot open shell due to the following issues:\n{}",
+                issues.join("\n")
+            ));
+        }
+
+        let mut public_key_file = File::open(public_key_path)?;
+        let mut public_key_bytes = [0u8; vach::PUBLIC_KEY_LENGTH];
+        public_key_file.read_exact(&mut public_key_bytes)?;
+        let public_key = VerifyingKey::from_bytes(&public_key_bytes)?;
+
+        let config = ArchiveConfig::default()
+            .magic(*ARCHIVE_MAGIC)
+            .key(public_key);
+        let target = File::open(&archive_path)?;
+        let mut archive = Archive::with_config(target, &config)?;
+
+        let file_index = archive.fetch_mut(ASSET_FILE_INDEX)?;
+        let files = String::from_utf8_lossy(&file_index.data).into_owned();
+        let mut lookup: HashMap<PathBuf, String> = HashMap::new();
+        for entry in files.split(ASSET_FILE_INDEX_SEP) {
+            if let Some((path, id)) = entry.split_once(ASSET_FILE_INDEX_KV_SEP) {
+                lookup.insert(PathBuf::from(path), id.to_string());
+            }
+        }
+
+        // `Archive`/`Resource` only ever hand back fully decoded bytes, so `stat` peeks
+        // at the registry on disk itself for the fields decoding throws away (on-disk
+        // size, compression algorithm, encrypted/signed flags); best-effort only, falls
+        // back to decoded-size-only if the file doesn't look like what we expect
+        let registry = peek_registry(&archive_path);
+
+        println!(
+            "bva shell — '{}' ({} files, {} leaves). Type 'help' for commands, 'exit' to quit.",
+            archive_path.to_string_lossy(),
+            lookup.len(),
+            lookup.values().collect::<std::collections::HashSet<_>>().len()
+        );
+
+        let mut cwd = PathBuf::new();
+        loop {
+            print!("{}> ", cwd.to_string_lossy());
+            io::stdout().flush()?;
+
+            let mut line = String::new();
+            if io::stdin().read_line(&mut line)? == 0 {
+                break;
+            }
+            let line = line.trim();
+            if line.is_empty() {
+                continue;
+            }
+
+            let mut parts = line.splitn(2, char::is_whitespace);
+            let command = parts.next().unwrap_or_default();
+            let arg = parts.next().unwrap_or_default().trim();
+
+            match command {
+                "help" => {
+                    println!("commands: ls [path], cd <path>, stat <path>, cat <path>, find <glob>, exit");
+                }
+                "exit" | "quit" => break,
+                "ls" => {
+                    let target = if arg.is_empty() { cwd.clone() } else { cwd.join(arg) };
+                    if is_directory_in(&lookup, &target) {
+                        for entry in read_directory_in(&lookup, &target) {
+                            println!("{}", entry.to_string_lossy());
+                        }
+                    } else {
+                        println!("not a directory: {}", target.to_string_lossy());
+                    }
+                }
+                "cd" => {
+                    let target = if arg.is_empty() {
+                        PathBuf::new()
+                    } else {
+                        cwd.join(arg)
+                    };
+                    if target == Path::new("") || is_directory_in(&lookup, &target) {
+                        cwd = target;
+                    } else {
+                        println!("not a directory: {arg}");
+                    }
+                }
+                "stat" => {
+                    let path = cwd.join(arg);
+                    match lookup.get(&path) {
+                        Some(id) => match archive.fetch_mut(id) {
+                            Ok(resource) => {
+                                let decoded_len = resource.data.len();
+                                match registry.get(id) {
+                                    Some(info) => println!(
+                                        "{} -> id {} [{decoded_len} bytes decoded, {} bytes on disk (unverified), algorithm={} (unverified), compressed={}, encrypted={}, signed={} (flags unverified)]",
+                                        path.to_string_lossy(),
+                                        id,
+                                        info.on_disk_len,
+                                        info.algorithm.unwrap_or("none"),
+                                        info.compressed,
+                                        info.encrypted,
+                                        info.signed,
+                                    ),
+                                    None => println!(
+                                        "{} -> id {} [{decoded_len} bytes decoded; extended metadata unavailable]",
+                                        path.to_string_lossy(),
+                                        id,
+                                    ),
+                                }
+                            }
+                            Err(err) => println!("error reading '{arg}': {err}"),
+                        },
+                        None => println!("not found: {arg}"),
+                    }
+                }
+                "cat" => {
+                    let path = cwd.join(arg);
+                    match lookup.get(&path).cloned() {
+                        Some(id) => match archive.fetch_mut(&id) {
+                            Ok(resource) => {
+                                io::stdout().write_all(&resource.data)?;
+                                println!();
+                            }
+                            Err(err) => println!("error reading '{arg}': {err}"),
+                        },
+                        None => println!("not found: {arg}"),
+                    }
+                }
+                "find" => {
+                    let Ok(pattern) = Pattern::new(arg) else {
+                        println!("invalid glob: {arg}");
+                        continue;
+                    };
+                    for path in lookup.keys() {
+                        if pattern.matches(&path.to_string_lossy()) {
+                            println!("{}", path.to_string_lossy());
+                        }
+                    }
+                }
+                other => println!("unknown command: {other} (type 'help')"),
+            }
+        }
+
+        Ok(())
+    }
+
+    #[derive(Clone, Copy, Debug)]
+    struct LeafInfo {
+        on_disk_len: u64,
+        compressed: bool,
+        encrypted: bool,
+        signed: bool,
+        algorithm: Option<&'static str>,
+    }
+
+    // note: GUESSES at vach's on-disk registry shape (magic, then a flags/version/count
+    //       header, then one variable-length entry per leaf) from outside the crate —
+    //       nothing here is validated against vach's real format, unlike the remote
+    //       reader (see wasm_remote in bevy_vach_assets), which deliberately moved away
+    //       from exactly this kind of hand-rolled parsing because it's easy to get subtly
+    //       wrong. It's read-only, only feeds `stat`'s display output, and degrades to
+    //       decoded-size-only on any parse failure, but a wrong (rather than failed) parse
+    //       can't be detected here, so treat everything it returns as unverified.
+    fn peek_registry(archive_path: &Path) -> HashMap<String, LeafInfo> {
+        let Ok(bytes) = std::fs::read(archive_path) else {
+            return HashMap::new();
+        };
+        parse_registry(&bytes).unwrap_or_default()
+    }
+
+    fn parse_registry(bytes: &[u8]) -> Option<HashMap<String, LeafInfo>> {
+        if bytes.len() < ARCHIVE_MAGIC.len() || &bytes[..ARCHIVE_MAGIC.len()] != ARCHIVE_MAGIC {
+            return None;
+        }
+
+        let mut cursor = ARCHIVE_MAGIC.len() + 4; // magic + flags(u16) + version(u16)
+        let count = read_u16(bytes, cursor)?;
+        cursor += 2;
+
+        let mut entries = HashMap::new();
+        for _ in 0..count {
+            let id_len = *bytes.get(cursor)? as usize;
+            cursor += 1;
+            let id = String::from_utf8_lossy(bytes.get(cursor..cursor + id_len)?).into_owned();
+            cursor += id_len;
+
+            let flags = *bytes.get(cursor)?;
+            cursor += 1;
+            let _offset = read_u64(bytes, cursor)?;
+            cursor += 8;
+            let length = read_u64(bytes, cursor)?;
+            cursor += 8;
+
+            entries.insert(
+                id,
+                LeafInfo {
+                    on_disk_len: length,
+                    compressed: flags & 0b0000_0001 != 0,
+                    encrypted: flags & 0b0000_0010 != 0,
+                    signed: flags & 0b0000_0100 != 0,
+                    algorithm: match (flags >> 3) & 0b11 {
+                        1 => Some("lz4"),
+                        2 => Some("snappy"),
+                        3 => Some("brotli"),
+                        _ => None,
+                    },
+                },
+            );
+        }
+
+        Some(entries)
+    }
+
+    fn read_u16(buf: &[u8], at: usize) -> Option<u16> {
+        Some(u16::from_le_bytes(buf.get(at..at + 2)?.try_into().ok()?))
+    }
+
+    fn read_u64(buf: &[u8], at: usize) -> Option<u64> {
+        Some(u64::from_le_bytes(buf.get(at..at + 8)?.try_into().ok()?))
+    }
+}
@@ -18,6 +18,11 @@ fn main() {
                 #[cfg(target_arch = "wasm32")]
                 // note: adjust the paths to your needs!
                 static_archive: Some(include_bytes!("../../../assets.bva")),
+
+                // note: only used on wasm32 when static_archive is None; fetches the archive
+                //       over HTTP (a Range request for its start, then the whole file if
+                //       needed) instead of embedding it
+                remote_url: None,
             },
             DefaultPlugins,
         ))